@@ -0,0 +1,75 @@
+use r2d2_sqlite::SqliteConnectionManager;
+
+pub type FlashDbManager = r2d2::Pool<SqliteConnectionManager>;
+
+#[derive(Debug)]
+pub enum DbError {
+    Pool(r2d2::Error),
+    Sqlite(rusqlite::Error),
+}
+
+impl From<r2d2::Error> for DbError {
+    fn from(err: r2d2::Error) -> DbError {
+        DbError::Pool(err)
+    }
+}
+
+impl From<rusqlite::Error> for DbError {
+    fn from(err: rusqlite::Error) -> DbError {
+        DbError::Sqlite(err)
+    }
+}
+
+impl std::fmt::Display for DbError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DbError::Pool(e) => write!(f, "database pool error: {}", e),
+            DbError::Sqlite(e) => write!(f, "database error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for DbError {}
+
+pub fn new_manager(connect_string: String) -> Result<FlashDbManager, DbError> {
+    // An anonymous `:memory:` database is private to the connection that
+    // opened it, so the pool must never hand out more than one connection
+    // or later callers would see an empty database.
+    let is_memory = connect_string == ":memory:";
+    let manager = SqliteConnectionManager::file(connect_string);
+    let mut builder = r2d2::Pool::builder();
+    if is_memory {
+        builder = builder.max_size(1);
+    }
+    let pool = builder.build(manager)?;
+    init_schema(&pool)?;
+    Ok(pool)
+}
+
+fn init_schema(pool: &FlashDbManager) -> Result<(), DbError> {
+    let conn = pool.get()?;
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS decks (
+            id INTEGER PRIMARY KEY,
+            owner_id INTEGER NOT NULL,
+            name TEXT NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS cards (
+            id INTEGER PRIMARY KEY,
+            deck_id INTEGER NOT NULL,
+            front TEXT NOT NULL,
+            back TEXT NOT NULL,
+            position INTEGER NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS scores (
+            card_id INTEGER NOT NULL,
+            account_id INTEGER NOT NULL,
+            n INTEGER NOT NULL DEFAULT 0,
+            ef REAL NOT NULL DEFAULT 2.5,
+            interval_days INTEGER NOT NULL DEFAULT 0,
+            due INTEGER NOT NULL DEFAULT 0,
+            PRIMARY KEY (card_id, account_id)
+        );",
+    )?;
+    Ok(())
+}
@@ -0,0 +1,142 @@
+use std::sync::Arc;
+
+use serde::Deserialize;
+use tokio::sync::Mutex;
+use webe_web::http::{Responder, WebeRequest, WebeResponse};
+
+use crate::http::account_id;
+use crate::{FlashError, FlashManager};
+
+fn status_for(err: &FlashError) -> u16 {
+    match err {
+        FlashError::NotFound => 404,
+        FlashError::Forbidden => 403,
+        FlashError::TooManyCards | FlashError::FieldTooLong => 413,
+        FlashError::Db(_) | FlashError::Id(_) => 500,
+    }
+}
+
+#[derive(Deserialize)]
+struct CreateCardRequest {
+    deck_id: u64,
+    front: String,
+    back: String,
+}
+
+pub struct CreateCardResponder {
+    flash_manager: Arc<Mutex<FlashManager>>,
+}
+
+impl CreateCardResponder {
+    pub fn new(flash_manager: Arc<Mutex<FlashManager>>) -> CreateCardResponder {
+        CreateCardResponder { flash_manager }
+    }
+}
+
+#[async_trait::async_trait]
+impl Responder for CreateCardResponder {
+    async fn respond(&self, req: WebeRequest) -> WebeResponse {
+        let Some(account_id) = account_id(&req) else { return WebeResponse::status(401) };
+        let Ok(body) = serde_json::from_slice::<CreateCardRequest>(&req.body) else {
+            return WebeResponse::status(400);
+        };
+        let flash_manager = self.flash_manager.lock().await;
+        match flash_manager.create_card(account_id, body.deck_id, &body.front, &body.back) {
+            Ok(card) => WebeResponse::json(201, &card),
+            Err(err) => WebeResponse::status(status_for(&err)),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct UpdateCardRequest {
+    card_id: u64,
+    front: String,
+    back: String,
+}
+
+pub struct UpdateCardResponder {
+    flash_manager: Arc<Mutex<FlashManager>>,
+}
+
+impl UpdateCardResponder {
+    pub fn new(flash_manager: Arc<Mutex<FlashManager>>) -> UpdateCardResponder {
+        UpdateCardResponder { flash_manager }
+    }
+}
+
+#[async_trait::async_trait]
+impl Responder for UpdateCardResponder {
+    async fn respond(&self, req: WebeRequest) -> WebeResponse {
+        let Some(account_id) = account_id(&req) else { return WebeResponse::status(401) };
+        let Ok(body) = serde_json::from_slice::<UpdateCardRequest>(&req.body) else {
+            return WebeResponse::status(400);
+        };
+        let flash_manager = self.flash_manager.lock().await;
+        match flash_manager.update_card(account_id, body.card_id, &body.front, &body.back) {
+            Ok(()) => WebeResponse::status(200),
+            Err(err) => WebeResponse::status(status_for(&err)),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct UpdateCardPositionRequest {
+    card_id: u64,
+    position: i64,
+}
+
+pub struct UpdateCardPositionResponder {
+    flash_manager: Arc<Mutex<FlashManager>>,
+}
+
+impl UpdateCardPositionResponder {
+    pub fn new(flash_manager: Arc<Mutex<FlashManager>>) -> UpdateCardPositionResponder {
+        UpdateCardPositionResponder { flash_manager }
+    }
+}
+
+#[async_trait::async_trait]
+impl Responder for UpdateCardPositionResponder {
+    async fn respond(&self, req: WebeRequest) -> WebeResponse {
+        let Some(account_id) = account_id(&req) else { return WebeResponse::status(401) };
+        let Ok(body) = serde_json::from_slice::<UpdateCardPositionRequest>(&req.body) else {
+            return WebeResponse::status(400);
+        };
+        let flash_manager = self.flash_manager.lock().await;
+        match flash_manager.update_card_position(account_id, body.card_id, body.position) {
+            Ok(()) => WebeResponse::status(200),
+            Err(err) => WebeResponse::status(status_for(&err)),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct DeleteCardRequest {
+    card_id: u64,
+}
+
+pub struct DeleteCardResponder {
+    flash_manager: Arc<Mutex<FlashManager>>,
+}
+
+impl DeleteCardResponder {
+    pub fn new(flash_manager: Arc<Mutex<FlashManager>>) -> DeleteCardResponder {
+        DeleteCardResponder { flash_manager }
+    }
+}
+
+#[async_trait::async_trait]
+impl Responder for DeleteCardResponder {
+    async fn respond(&self, req: WebeRequest) -> WebeResponse {
+        let Some(account_id) = account_id(&req) else { return WebeResponse::status(401) };
+        let Ok(body) = serde_json::from_slice::<DeleteCardRequest>(&req.body) else {
+            return WebeResponse::status(400);
+        };
+        let flash_manager = self.flash_manager.lock().await;
+        match flash_manager.delete_card(account_id, body.card_id) {
+            Ok(()) => WebeResponse::status(200),
+            Err(err) => WebeResponse::status(status_for(&err)),
+        }
+    }
+}
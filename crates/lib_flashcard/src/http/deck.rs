@@ -0,0 +1,221 @@
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+use webe_web::http::{Responder, WebeRequest, WebeResponse};
+
+use crate::http::account_id;
+use crate::{CardExport, FlashError, FlashManager};
+
+fn status_for(err: &FlashError) -> u16 {
+    match err {
+        FlashError::NotFound => 404,
+        FlashError::Forbidden => 403,
+        FlashError::TooManyCards | FlashError::FieldTooLong => 413,
+        FlashError::Db(_) | FlashError::Id(_) => 500,
+    }
+}
+
+pub struct DecksResponder {
+    flash_manager: Arc<Mutex<FlashManager>>,
+}
+
+impl DecksResponder {
+    pub fn new(flash_manager: Arc<Mutex<FlashManager>>) -> DecksResponder {
+        DecksResponder { flash_manager }
+    }
+}
+
+#[async_trait::async_trait]
+impl Responder for DecksResponder {
+    async fn respond(&self, req: WebeRequest) -> WebeResponse {
+        let Some(account_id) = account_id(&req) else { return WebeResponse::status(401) };
+        let flash_manager = self.flash_manager.lock().await;
+        match flash_manager.list_decks(account_id) {
+            Ok(decks) => WebeResponse::json(200, &decks),
+            Err(err) => WebeResponse::status(status_for(&err)),
+        }
+    }
+}
+
+pub struct DeckDetailsResponder {
+    flash_manager: Arc<Mutex<FlashManager>>,
+    param: String,
+}
+
+impl DeckDetailsResponder {
+    pub fn new(flash_manager: Arc<Mutex<FlashManager>>, param: String) -> DeckDetailsResponder {
+        DeckDetailsResponder { flash_manager, param }
+    }
+}
+
+#[derive(Serialize)]
+struct DeckDetails {
+    id: u64,
+    name: String,
+    cards: Vec<crate::Card>,
+}
+
+#[async_trait::async_trait]
+impl Responder for DeckDetailsResponder {
+    async fn respond(&self, req: WebeRequest) -> WebeResponse {
+        let Some(account_id) = account_id(&req) else { return WebeResponse::status(401) };
+        let Some(deck_id) = req.params.get(&self.param).and_then(|v| v.parse::<u64>().ok()) else {
+            return WebeResponse::status(400);
+        };
+        let flash_manager = self.flash_manager.lock().await;
+        match flash_manager.deck_details(account_id, deck_id) {
+            Ok((deck, cards)) => WebeResponse::json(200, &DeckDetails { id: deck.id, name: deck.name, cards }),
+            Err(err) => WebeResponse::status(status_for(&err)),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct CreateDeckRequest {
+    name: String,
+}
+
+pub struct CreateDeckResponder {
+    flash_manager: Arc<Mutex<FlashManager>>,
+}
+
+impl CreateDeckResponder {
+    pub fn new(flash_manager: Arc<Mutex<FlashManager>>) -> CreateDeckResponder {
+        CreateDeckResponder { flash_manager }
+    }
+}
+
+#[async_trait::async_trait]
+impl Responder for CreateDeckResponder {
+    async fn respond(&self, req: WebeRequest) -> WebeResponse {
+        let Some(account_id) = account_id(&req) else { return WebeResponse::status(401) };
+        let Ok(body) = serde_json::from_slice::<CreateDeckRequest>(&req.body) else {
+            return WebeResponse::status(400);
+        };
+        let flash_manager = self.flash_manager.lock().await;
+        match flash_manager.create_deck(account_id, &body.name) {
+            Ok(deck) => WebeResponse::json(201, &deck),
+            Err(err) => WebeResponse::status(status_for(&err)),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct UpdateDeckRequest {
+    deck_id: u64,
+    name: String,
+}
+
+pub struct UpdateDeckResponder {
+    flash_manager: Arc<Mutex<FlashManager>>,
+}
+
+impl UpdateDeckResponder {
+    pub fn new(flash_manager: Arc<Mutex<FlashManager>>) -> UpdateDeckResponder {
+        UpdateDeckResponder { flash_manager }
+    }
+}
+
+#[async_trait::async_trait]
+impl Responder for UpdateDeckResponder {
+    async fn respond(&self, req: WebeRequest) -> WebeResponse {
+        let Some(account_id) = account_id(&req) else { return WebeResponse::status(401) };
+        let Ok(body) = serde_json::from_slice::<UpdateDeckRequest>(&req.body) else {
+            return WebeResponse::status(400);
+        };
+        let flash_manager = self.flash_manager.lock().await;
+        match flash_manager.rename_deck(account_id, body.deck_id, &body.name) {
+            Ok(()) => WebeResponse::status(200),
+            Err(err) => WebeResponse::status(status_for(&err)),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct DeleteDeckRequest {
+    deck_id: u64,
+}
+
+pub struct DeleteDeckResponder {
+    flash_manager: Arc<Mutex<FlashManager>>,
+}
+
+impl DeleteDeckResponder {
+    pub fn new(flash_manager: Arc<Mutex<FlashManager>>) -> DeleteDeckResponder {
+        DeleteDeckResponder { flash_manager }
+    }
+}
+
+#[async_trait::async_trait]
+impl Responder for DeleteDeckResponder {
+    async fn respond(&self, req: WebeRequest) -> WebeResponse {
+        let Some(account_id) = account_id(&req) else { return WebeResponse::status(401) };
+        let Ok(body) = serde_json::from_slice::<DeleteDeckRequest>(&req.body) else {
+            return WebeResponse::status(400);
+        };
+        let flash_manager = self.flash_manager.lock().await;
+        match flash_manager.delete_deck(account_id, body.deck_id) {
+            Ok(()) => WebeResponse::status(200),
+            Err(err) => WebeResponse::status(status_for(&err)),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ImportDeckRequest {
+    name: String,
+    cards: Vec<CardExport>,
+}
+
+/// Batch-creates a deck and all of its cards from an exported JSON shape.
+pub struct ImportDeckResponder {
+    flash_manager: Arc<Mutex<FlashManager>>,
+}
+
+impl ImportDeckResponder {
+    pub fn new(flash_manager: Arc<Mutex<FlashManager>>) -> ImportDeckResponder {
+        ImportDeckResponder { flash_manager }
+    }
+}
+
+#[async_trait::async_trait]
+impl Responder for ImportDeckResponder {
+    async fn respond(&self, req: WebeRequest) -> WebeResponse {
+        let Some(account_id) = account_id(&req) else { return WebeResponse::status(401) };
+        let Ok(body) = serde_json::from_slice::<ImportDeckRequest>(&req.body) else {
+            return WebeResponse::status(400);
+        };
+        let flash_manager = self.flash_manager.lock().await;
+        match flash_manager.import_deck(account_id, &body.name, body.cards) {
+            Ok(deck) => WebeResponse::json(201, &deck),
+            Err(err) => WebeResponse::status(status_for(&err)),
+        }
+    }
+}
+
+pub struct ExportDeckResponder {
+    flash_manager: Arc<Mutex<FlashManager>>,
+    param: String,
+}
+
+impl ExportDeckResponder {
+    pub fn new(flash_manager: Arc<Mutex<FlashManager>>, param: String) -> ExportDeckResponder {
+        ExportDeckResponder { flash_manager, param }
+    }
+}
+
+#[async_trait::async_trait]
+impl Responder for ExportDeckResponder {
+    async fn respond(&self, req: WebeRequest) -> WebeResponse {
+        let Some(account_id) = account_id(&req) else { return WebeResponse::status(401) };
+        let Some(deck_id) = req.params.get(&self.param).and_then(|v| v.parse::<u64>().ok()) else {
+            return WebeResponse::status(400);
+        };
+        let flash_manager = self.flash_manager.lock().await;
+        match flash_manager.export_deck(account_id, deck_id) {
+            Ok(export) => WebeResponse::json(200, &export),
+            Err(err) => WebeResponse::status(status_for(&err)),
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use std::sync::Arc;
+
+use serde::Deserialize;
+use tokio::sync::Mutex;
+use webe_web::http::{Responder, WebeRequest, WebeResponse};
+
+use crate::http::account_id;
+use crate::{FlashError, FlashManager};
+
+fn status_for(err: &FlashError) -> u16 {
+    match err {
+        FlashError::NotFound => 404,
+        FlashError::Forbidden => 403,
+        FlashError::TooManyCards | FlashError::FieldTooLong => 413,
+        FlashError::Db(_) | FlashError::Id(_) => 500,
+    }
+}
+
+#[derive(Deserialize)]
+struct UpdateScoreRequest {
+    card_id: u64,
+    q: u8,
+}
+
+/// Scores one review of a card, advancing its SM-2 schedule (`sm2::review`).
+pub struct UpdateScoreResponder {
+    flash_manager: Arc<Mutex<FlashManager>>,
+}
+
+impl UpdateScoreResponder {
+    pub fn new(flash_manager: Arc<Mutex<FlashManager>>) -> UpdateScoreResponder {
+        UpdateScoreResponder { flash_manager }
+    }
+}
+
+#[async_trait::async_trait]
+impl Responder for UpdateScoreResponder {
+    async fn respond(&self, req: WebeRequest) -> WebeResponse {
+        let Some(account_id) = account_id(&req) else { return WebeResponse::status(401) };
+        let Ok(body) = serde_json::from_slice::<UpdateScoreRequest>(&req.body) else {
+            return WebeResponse::status(400);
+        };
+        if body.q > 5 {
+            return WebeResponse::status(400);
+        }
+        let flash_manager = self.flash_manager.lock().await;
+        match flash_manager.update_score(account_id, body.card_id, body.q) {
+            Ok(score) => WebeResponse::json(200, &score),
+            Err(err) => WebeResponse::status(status_for(&err)),
+        }
+    }
+}
+
+pub struct DeckScoresResponder {
+    flash_manager: Arc<Mutex<FlashManager>>,
+    param: String,
+}
+
+impl DeckScoresResponder {
+    pub fn new(flash_manager: Arc<Mutex<FlashManager>>, param: String) -> DeckScoresResponder {
+        DeckScoresResponder { flash_manager, param }
+    }
+}
+
+#[async_trait::async_trait]
+impl Responder for DeckScoresResponder {
+    async fn respond(&self, req: WebeRequest) -> WebeResponse {
+        let Some(account_id) = account_id(&req) else { return WebeResponse::status(401) };
+        let Some(deck_id) = req.params.get(&self.param).and_then(|v| v.parse::<u64>().ok()) else {
+            return WebeResponse::status(400);
+        };
+        let flash_manager = self.flash_manager.lock().await;
+        match flash_manager.deck_scores(account_id, deck_id) {
+            Ok(scores) => WebeResponse::json(200, &scores),
+            Err(err) => WebeResponse::status(status_for(&err)),
+        }
+    }
+}
+
+/// Same shape as `DeckScoresResponder`, filtered to cards due for review now.
+pub struct DeckDueResponder {
+    flash_manager: Arc<Mutex<FlashManager>>,
+    param: String,
+}
+
+impl DeckDueResponder {
+    pub fn new(flash_manager: Arc<Mutex<FlashManager>>, param: String) -> DeckDueResponder {
+        DeckDueResponder { flash_manager, param }
+    }
+}
+
+#[async_trait::async_trait]
+impl Responder for DeckDueResponder {
+    async fn respond(&self, req: WebeRequest) -> WebeResponse {
+        let Some(account_id) = account_id(&req) else { return WebeResponse::status(401) };
+        let Some(deck_id) = req.params.get(&self.param).and_then(|v| v.parse::<u64>().ok()) else {
+            return WebeResponse::status(400);
+        };
+        let flash_manager = self.flash_manager.lock().await;
+        match flash_manager.deck_due(account_id, deck_id) {
+            Ok(scores) => WebeResponse::json(200, &scores),
+            Err(err) => WebeResponse::status(status_for(&err)),
+        }
+    }
+}
@@ -0,0 +1,10 @@
+pub mod card;
+pub mod deck;
+pub mod game;
+
+use webe_web::http::{WebeRequest, ACCOUNT_ID_PARAM};
+
+pub(crate) fn account_id(req: &WebeRequest) -> Option<u64> {
+    req.params.get(ACCOUNT_ID_PARAM).and_then(|v| v.parse::<u64>().ok())
+}
+
@@ -0,0 +1,462 @@
+pub mod db;
+pub mod http;
+pub mod sm2;
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rusqlite::OptionalExtension;
+use serde::Serialize;
+use tokio::sync::mpsc;
+use webe_id::WebeIDFactory;
+use webe_web::responders::ws::BroadcastSource;
+
+pub const MAX_IMPORT_CARDS: usize = 2_000;
+pub const MAX_FIELD_LEN: usize = 4_000;
+
+#[derive(Debug)]
+pub enum FlashError {
+    Db(db::DbError),
+    Id(webe_id::WebeIDError),
+    NotFound,
+    Forbidden,
+    TooManyCards,
+    FieldTooLong,
+}
+
+impl From<db::DbError> for FlashError {
+    fn from(err: db::DbError) -> FlashError {
+        FlashError::Db(err)
+    }
+}
+
+impl From<rusqlite::Error> for FlashError {
+    fn from(err: rusqlite::Error) -> FlashError {
+        FlashError::Db(db::DbError::from(err))
+    }
+}
+
+impl From<r2d2::Error> for FlashError {
+    fn from(err: r2d2::Error) -> FlashError {
+        FlashError::Db(db::DbError::from(err))
+    }
+}
+
+impl From<webe_id::WebeIDError> for FlashError {
+    fn from(err: webe_id::WebeIDError) -> FlashError {
+        FlashError::Id(err)
+    }
+}
+
+impl std::fmt::Display for FlashError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl std::error::Error for FlashError {}
+
+fn now() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).expect("system clock before epoch").as_secs() as i64
+}
+
+#[derive(Serialize, Clone)]
+pub struct Deck {
+    pub id: u64,
+    pub name: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct Card {
+    pub id: u64,
+    pub deck_id: u64,
+    pub front: String,
+    pub back: String,
+    pub position: i64,
+}
+
+#[derive(Serialize, Clone)]
+pub struct CardScore {
+    pub card_id: u64,
+    pub n: i64,
+    pub ef: f64,
+    pub interval_days: i64,
+    pub due: i64,
+}
+
+#[derive(Serialize)]
+pub struct DeckExport {
+    pub name: String,
+    pub cards: Vec<CardExport>,
+}
+
+#[derive(serde::Deserialize, Serialize)]
+pub struct CardExport {
+    pub front: String,
+    pub back: String,
+    pub position: i64,
+}
+
+/// broadcast event published to every open `/ws` connection for the
+/// account that owns the mutated card's deck. Only card mutations (create,
+/// update, move, delete) publish events; deck create, rename, delete and
+/// import publish none.
+#[derive(Serialize, serde::Deserialize, Clone)]
+pub struct ChangeEvent {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub deck_id: u64,
+    pub card_id: Option<u64>,
+}
+
+type BroadcastRegistry = HashMap<u64, Vec<mpsc::Sender<String>>>;
+
+pub struct FlashManager {
+    pub db_manager: db::FlashDbManager,
+    pub id_factory: Arc<StdMutex<WebeIDFactory>>,
+    pub broadcast: Arc<StdMutex<BroadcastRegistry>>,
+}
+
+impl FlashManager {
+    fn next_id(&self) -> Result<u64, FlashError> {
+        let mut factory = self.id_factory.lock().expect("id factory mutex poisoned");
+        Ok(factory.next()?)
+    }
+
+    fn publish(&self, account_id: u64, event: ChangeEvent) {
+        let payload = match serde_json::to_string(&event) {
+            Ok(payload) => payload,
+            Err(_) => return,
+        };
+        let mut registry = self.broadcast.lock().expect("broadcast registry mutex poisoned");
+        if let Some(senders) = registry.get_mut(&account_id) {
+            senders.retain(|sender| sender.try_send(payload.clone()).is_ok());
+        }
+    }
+
+    fn assert_owner(&self, account_id: u64, deck_id: u64) -> Result<(), FlashError> {
+        let conn = self.db_manager.get()?;
+        let owner_id: Option<i64> = conn
+            .query_row("SELECT owner_id FROM decks WHERE id = ?1", [deck_id as i64], |row| row.get(0))
+            .optional()?;
+        match owner_id {
+            Some(owner_id) if owner_id as u64 == account_id => Ok(()),
+            Some(_) => Err(FlashError::Forbidden),
+            None => Err(FlashError::NotFound),
+        }
+    }
+
+    fn deck_id_of_card(&self, card_id: u64) -> Result<u64, FlashError> {
+        let conn = self.db_manager.get()?;
+        let deck_id: Option<i64> = conn
+            .query_row("SELECT deck_id FROM cards WHERE id = ?1", [card_id as i64], |row| row.get(0))
+            .optional()?;
+        Ok(deck_id.ok_or(FlashError::NotFound)? as u64)
+    }
+
+    pub fn list_decks(&self, account_id: u64) -> Result<Vec<Deck>, FlashError> {
+        let conn = self.db_manager.get()?;
+        let mut stmt = conn.prepare("SELECT id, name FROM decks WHERE owner_id = ?1")?;
+        let decks = stmt
+            .query_map([account_id as i64], |row| {
+                Ok(Deck { id: row.get::<_, i64>(0)? as u64, name: row.get(1)? })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(decks)
+    }
+
+    pub fn deck_details(&self, account_id: u64, deck_id: u64) -> Result<(Deck, Vec<Card>), FlashError> {
+        self.assert_owner(account_id, deck_id)?;
+        let conn = self.db_manager.get()?;
+        let name: String =
+            conn.query_row("SELECT name FROM decks WHERE id = ?1", [deck_id as i64], |row| row.get(0))?;
+        let mut stmt =
+            conn.prepare("SELECT id, deck_id, front, back, position FROM cards WHERE deck_id = ?1 ORDER BY position")?;
+        let cards = stmt
+            .query_map([deck_id as i64], |row| {
+                Ok(Card {
+                    id: row.get::<_, i64>(0)? as u64,
+                    deck_id: row.get::<_, i64>(1)? as u64,
+                    front: row.get(2)?,
+                    back: row.get(3)?,
+                    position: row.get(4)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok((Deck { id: deck_id, name }, cards))
+    }
+
+    pub fn create_deck(&self, account_id: u64, name: &str) -> Result<Deck, FlashError> {
+        let deck_id = self.next_id()?;
+        let conn = self.db_manager.get()?;
+        conn.execute(
+            "INSERT INTO decks (id, owner_id, name) VALUES (?1, ?2, ?3)",
+            rusqlite::params![deck_id as i64, account_id as i64, name],
+        )?;
+        Ok(Deck { id: deck_id, name: name.to_string() })
+    }
+
+    pub fn rename_deck(&self, account_id: u64, deck_id: u64, name: &str) -> Result<(), FlashError> {
+        self.assert_owner(account_id, deck_id)?;
+        let conn = self.db_manager.get()?;
+        conn.execute("UPDATE decks SET name = ?1 WHERE id = ?2", rusqlite::params![name, deck_id as i64])?;
+        Ok(())
+    }
+
+    pub fn delete_deck(&self, account_id: u64, deck_id: u64) -> Result<(), FlashError> {
+        self.assert_owner(account_id, deck_id)?;
+        let conn = self.db_manager.get()?;
+        conn.execute(
+            "DELETE FROM scores WHERE card_id IN (SELECT id FROM cards WHERE deck_id = ?1)",
+            [deck_id as i64],
+        )?;
+        conn.execute("DELETE FROM cards WHERE deck_id = ?1", [deck_id as i64])?;
+        conn.execute("DELETE FROM decks WHERE id = ?1", [deck_id as i64])?;
+        Ok(())
+    }
+
+    pub fn create_card(&self, account_id: u64, deck_id: u64, front: &str, back: &str) -> Result<Card, FlashError> {
+        self.assert_owner(account_id, deck_id)?;
+        let card_id = self.next_id()?;
+        let conn = self.db_manager.get()?;
+        let position: i64 = conn.query_row(
+            "SELECT COALESCE(MAX(position) + 1, 0) FROM cards WHERE deck_id = ?1",
+            [deck_id as i64],
+            |row| row.get(0),
+        )?;
+        conn.execute(
+            "INSERT INTO cards (id, deck_id, front, back, position) VALUES (?1, ?2, ?3, ?4, ?5)",
+            rusqlite::params![card_id as i64, deck_id as i64, front, back, position],
+        )?;
+        drop(conn);
+        self.publish(account_id, ChangeEvent { kind: "card_created".to_string(), deck_id, card_id: Some(card_id) });
+        Ok(Card { id: card_id, deck_id, front: front.to_string(), back: back.to_string(), position })
+    }
+
+    pub fn update_card(&self, account_id: u64, card_id: u64, front: &str, back: &str) -> Result<(), FlashError> {
+        let deck_id = self.deck_id_of_card(card_id)?;
+        self.assert_owner(account_id, deck_id)?;
+        let conn = self.db_manager.get()?;
+        conn.execute(
+            "UPDATE cards SET front = ?1, back = ?2 WHERE id = ?3",
+            rusqlite::params![front, back, card_id as i64],
+        )?;
+        drop(conn);
+        self.publish(account_id, ChangeEvent { kind: "card_updated".to_string(), deck_id, card_id: Some(card_id) });
+        Ok(())
+    }
+
+    pub fn update_card_position(&self, account_id: u64, card_id: u64, position: i64) -> Result<(), FlashError> {
+        let deck_id = self.deck_id_of_card(card_id)?;
+        self.assert_owner(account_id, deck_id)?;
+        let conn = self.db_manager.get()?;
+        conn.execute("UPDATE cards SET position = ?1 WHERE id = ?2", rusqlite::params![position, card_id as i64])?;
+        drop(conn);
+        self.publish(account_id, ChangeEvent { kind: "card_moved".to_string(), deck_id, card_id: Some(card_id) });
+        Ok(())
+    }
+
+    pub fn delete_card(&self, account_id: u64, card_id: u64) -> Result<(), FlashError> {
+        let deck_id = self.deck_id_of_card(card_id)?;
+        self.assert_owner(account_id, deck_id)?;
+        let conn = self.db_manager.get()?;
+        conn.execute("DELETE FROM scores WHERE card_id = ?1", [card_id as i64])?;
+        conn.execute("DELETE FROM cards WHERE id = ?1", [card_id as i64])?;
+        drop(conn);
+        self.publish(account_id, ChangeEvent { kind: "card_deleted".to_string(), deck_id, card_id: Some(card_id) });
+        Ok(())
+    }
+
+    /// Applies one SM-2 review (see `sm2::review`) and persists the result.
+    pub fn update_score(&self, account_id: u64, card_id: u64, q: u8) -> Result<CardScore, FlashError> {
+        let deck_id = self.deck_id_of_card(card_id)?;
+        self.assert_owner(account_id, deck_id)?;
+        let conn = self.db_manager.get()?;
+        let current = conn
+            .query_row(
+                "SELECT n, ef, interval_days, due FROM scores WHERE card_id = ?1 AND account_id = ?2",
+                rusqlite::params![card_id as i64, account_id as i64],
+                |row| {
+                    Ok(sm2::ScoreState {
+                        n: row.get(0)?,
+                        ef: row.get(1)?,
+                        interval_days: row.get(2)?,
+                        due: row.get(3)?,
+                    })
+                },
+            )
+            .optional()?
+            .unwrap_or_default();
+        let updated = sm2::review(current, q, now());
+        conn.execute(
+            "INSERT INTO scores (card_id, account_id, n, ef, interval_days, due) VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(card_id, account_id) DO UPDATE SET n = excluded.n, ef = excluded.ef, interval_days = excluded.interval_days, due = excluded.due",
+            rusqlite::params![card_id as i64, account_id as i64, updated.n, updated.ef, updated.interval_days, updated.due],
+        )?;
+        Ok(CardScore { card_id, n: updated.n, ef: updated.ef, interval_days: updated.interval_days, due: updated.due })
+    }
+
+    pub fn deck_scores(&self, account_id: u64, deck_id: u64) -> Result<Vec<CardScore>, FlashError> {
+        self.assert_owner(account_id, deck_id)?;
+        let conn = self.db_manager.get()?;
+        let mut stmt = conn.prepare(
+            "SELECT c.id, COALESCE(s.n, 0), COALESCE(s.ef, 2.5), COALESCE(s.interval_days, 0), COALESCE(s.due, 0)
+             FROM cards c LEFT JOIN scores s ON s.card_id = c.id AND s.account_id = ?2
+             WHERE c.deck_id = ?1",
+        )?;
+        let scores = stmt
+            .query_map(rusqlite::params![deck_id as i64, account_id as i64], |row| {
+                Ok(CardScore {
+                    card_id: row.get::<_, i64>(0)? as u64,
+                    n: row.get(1)?,
+                    ef: row.get(2)?,
+                    interval_days: row.get(3)?,
+                    due: row.get(4)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(scores)
+    }
+
+    /// Same as `deck_scores` but filtered down to cards due for review now.
+    pub fn deck_due(&self, account_id: u64, deck_id: u64) -> Result<Vec<CardScore>, FlashError> {
+        let scores = self.deck_scores(account_id, deck_id)?;
+        let current = now();
+        Ok(scores.into_iter().filter(|s| s.due <= current).collect())
+    }
+
+    pub fn export_deck(&self, account_id: u64, deck_id: u64) -> Result<DeckExport, FlashError> {
+        let (deck, cards) = self.deck_details(account_id, deck_id)?;
+        Ok(DeckExport {
+            name: deck.name,
+            cards: cards.into_iter().map(|c| CardExport { front: c.front, back: c.back, position: c.position }).collect(),
+        })
+    }
+
+    /// Creates a new deck owned by `account_id` and batch-inserts `cards`
+    /// in a single transaction, validating count and field sizes first.
+    pub fn import_deck(&self, account_id: u64, name: &str, cards: Vec<CardExport>) -> Result<Deck, FlashError> {
+        if cards.len() > MAX_IMPORT_CARDS {
+            return Err(FlashError::TooManyCards);
+        }
+        for card in &cards {
+            if card.front.len() > MAX_FIELD_LEN || card.back.len() > MAX_FIELD_LEN {
+                return Err(FlashError::FieldTooLong);
+            }
+        }
+        let deck_id = self.next_id()?;
+        let mut conn = self.db_manager.get()?;
+        let tx = conn.transaction()?;
+        tx.execute(
+            "INSERT INTO decks (id, owner_id, name) VALUES (?1, ?2, ?3)",
+            rusqlite::params![deck_id as i64, account_id as i64, name],
+        )?;
+        for card in &cards {
+            let card_id = self.next_id()?;
+            tx.execute(
+                "INSERT INTO cards (id, deck_id, front, back, position) VALUES (?1, ?2, ?3, ?4, ?5)",
+                rusqlite::params![card_id as i64, deck_id as i64, card.front, card.back, card.position],
+            )?;
+        }
+        tx.commit()?;
+        Ok(Deck { id: deck_id, name: name.to_string() })
+    }
+}
+
+#[async_trait::async_trait]
+impl BroadcastSource for FlashManager {
+    async fn subscribe(&self, account_id: u64) -> mpsc::Receiver<String> {
+        let (sender, receiver) = mpsc::channel(32);
+        let mut registry = self.broadcast.lock().expect("broadcast registry mutex poisoned");
+        registry.entry(account_id).or_default().push(sender);
+        receiver
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn test_manager() -> FlashManager {
+        let db_manager = db::new_manager(":memory:".to_string()).expect("failed to create in-memory db");
+        let epoch = UNIX_EPOCH.checked_add(Duration::from_millis(1546300800000)).unwrap();
+        let id_factory = Arc::new(StdMutex::new(WebeIDFactory::new(epoch, 0u8).unwrap()));
+        FlashManager { db_manager, id_factory, broadcast: Arc::new(StdMutex::new(HashMap::new())) }
+    }
+
+    #[tokio::test]
+    async fn subscribers_receive_change_events_for_their_own_account() {
+        let manager = test_manager();
+        let deck = manager.create_deck(1, "Spanish").unwrap();
+        let mut events = manager.subscribe(1).await;
+        let card = manager.create_card(1, deck.id, "hola", "hello").unwrap();
+        let payload = events.try_recv().expect("subscriber should see the create event");
+        let event: ChangeEvent = serde_json::from_str(&payload).unwrap();
+        assert_eq!(event.kind, "card_created");
+        assert_eq!(event.card_id, Some(card.id));
+    }
+
+    #[tokio::test]
+    async fn subscribers_do_not_receive_other_accounts_events() {
+        let manager = test_manager();
+        let deck = manager.create_deck(1, "Spanish").unwrap();
+        let mut events = manager.subscribe(2).await;
+        manager.create_card(1, deck.id, "hola", "hello").unwrap();
+        assert!(events.try_recv().is_err(), "subscriber on a different account should not see the event");
+    }
+
+    #[test]
+    fn create_card_and_score_it() {
+        let manager = test_manager();
+        let deck = manager.create_deck(1, "Spanish").unwrap();
+        let card = manager.create_card(1, deck.id, "hola", "hello").unwrap();
+        let score = manager.update_score(1, card.id, 5).unwrap();
+        assert_eq!(score.n, 1);
+        assert_eq!(score.interval_days, 1);
+    }
+
+    #[test]
+    fn due_filters_out_future_cards() {
+        let manager = test_manager();
+        let deck = manager.create_deck(1, "Spanish").unwrap();
+        let card = manager.create_card(1, deck.id, "hola", "hello").unwrap();
+        manager.update_score(1, card.id, 5).unwrap();
+        let due = manager.deck_due(1, deck.id).unwrap();
+        assert!(due.is_empty(), "freshly scored card should not be due yet");
+    }
+
+    #[test]
+    fn other_accounts_cannot_touch_the_deck() {
+        let manager = test_manager();
+        let deck = manager.create_deck(1, "Spanish").unwrap();
+        assert!(matches!(manager.create_card(2, deck.id, "a", "b"), Err(FlashError::Forbidden)));
+    }
+
+    #[test]
+    fn import_rejects_too_many_cards() {
+        let manager = test_manager();
+        let cards: Vec<CardExport> =
+            (0..(MAX_IMPORT_CARDS + 1)).map(|i| CardExport { front: "f".to_string(), back: "b".to_string(), position: i as i64 }).collect();
+        assert!(matches!(manager.import_deck(1, "Big", cards), Err(FlashError::TooManyCards)));
+    }
+
+    #[test]
+    fn import_rejects_oversized_fields() {
+        let manager = test_manager();
+        let cards = vec![CardExport { front: "f".repeat(MAX_FIELD_LEN + 1), back: "b".to_string(), position: 0 }];
+        assert!(matches!(manager.import_deck(1, "Big", cards), Err(FlashError::FieldTooLong)));
+    }
+
+    #[test]
+    fn import_then_export_round_trips() {
+        let manager = test_manager();
+        let cards = vec![CardExport { front: "hola".to_string(), back: "hello".to_string(), position: 0 }];
+        let deck = manager.import_deck(1, "Spanish", cards).unwrap();
+        let export = manager.export_deck(1, deck.id).unwrap();
+        assert_eq!(export.name, "Spanish");
+        assert_eq!(export.cards.len(), 1);
+        assert_eq!(export.cards[0].front, "hola");
+    }
+}
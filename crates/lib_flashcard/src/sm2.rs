@@ -0,0 +1,88 @@
+/// Per-card, per-user SM-2 scheduling state.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScoreState {
+    pub n: i64,
+    pub ef: f64,
+    pub interval_days: i64,
+    pub due: i64,
+}
+
+impl Default for ScoreState {
+    fn default() -> ScoreState {
+        ScoreState { n: 0, ef: 2.5, interval_days: 0, due: 0 }
+    }
+}
+
+const MIN_EF: f64 = 1.3;
+
+/// Applies one SM-2 review of quality `q` (0..=5) to `state`, returning the
+/// updated state. `now` is the current unix timestamp in seconds.
+pub fn review(state: ScoreState, q: u8, now: i64) -> ScoreState {
+    let q = q.min(5) as f64;
+    let mut ef = state.ef + (0.1 - (5.0 - q) * (0.08 + (5.0 - q) * 0.02));
+    if ef < MIN_EF {
+        ef = MIN_EF;
+    }
+    let (n, interval_days) = if q >= 3.0 {
+        let interval_days = if state.n == 0 {
+            1
+        } else if state.n == 1 {
+            6
+        } else {
+            (state.interval_days as f64 * state.ef).round() as i64
+        };
+        (state.n + 1, interval_days)
+    } else {
+        (0, 1)
+    };
+    let due = now + interval_days * 86_400;
+    ScoreState { n, ef, interval_days, due }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_good_review_sets_one_day_interval() {
+        let state = review(ScoreState::default(), 4, 0);
+        assert_eq!(state.n, 1);
+        assert_eq!(state.interval_days, 1);
+        assert_eq!(state.due, 86_400);
+    }
+
+    #[test]
+    fn second_good_review_sets_six_day_interval() {
+        let first = review(ScoreState::default(), 4, 0);
+        let second = review(first, 4, 86_400);
+        assert_eq!(second.n, 2);
+        assert_eq!(second.interval_days, 6);
+    }
+
+    #[test]
+    fn third_good_review_multiplies_by_easiness() {
+        let first = review(ScoreState::default(), 5, 0);
+        let second = review(first, 5, 86_400);
+        let third = review(second, 5, 2 * 86_400);
+        assert_eq!(third.n, 3);
+        assert_eq!(third.interval_days, (second.interval_days as f64 * second.ef).round() as i64);
+    }
+
+    #[test]
+    fn failing_quality_resets_repetitions() {
+        let first = review(ScoreState::default(), 5, 0);
+        let second = review(first, 5, 86_400);
+        let failed = review(second, 2, 2 * 86_400);
+        assert_eq!(failed.n, 0);
+        assert_eq!(failed.interval_days, 1);
+    }
+
+    #[test]
+    fn easiness_factor_has_a_floor() {
+        let mut state = ScoreState::default();
+        for _ in 0..20 {
+            state = review(state, 0, 0);
+        }
+        assert!(state.ef >= MIN_EF);
+    }
+}
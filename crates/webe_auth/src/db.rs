@@ -0,0 +1,86 @@
+use r2d2_sqlite::SqliteConnectionManager;
+
+pub type DbManager = r2d2::Pool<SqliteConnectionManager>;
+
+#[derive(Debug)]
+pub enum DbError {
+    Pool(r2d2::Error),
+    Sqlite(rusqlite::Error),
+}
+
+impl From<r2d2::Error> for DbError {
+    fn from(err: r2d2::Error) -> DbError {
+        DbError::Pool(err)
+    }
+}
+
+impl From<rusqlite::Error> for DbError {
+    fn from(err: rusqlite::Error) -> DbError {
+        DbError::Sqlite(err)
+    }
+}
+
+impl std::fmt::Display for DbError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DbError::Pool(e) => write!(f, "database pool error: {}", e),
+            DbError::Sqlite(e) => write!(f, "database error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for DbError {}
+
+/// `connect_string` is a sqlite file path (or `:memory:`); only SQLite is
+/// supported.
+pub fn new_manager(connect_string: String) -> Result<DbManager, DbError> {
+    // An anonymous `:memory:` database is private to the connection that
+    // opened it, so the pool must never hand out more than one connection
+    // or later callers would see an empty database.
+    let is_memory = connect_string == ":memory:";
+    let manager = SqliteConnectionManager::file(connect_string);
+    let mut builder = r2d2::Pool::builder();
+    if is_memory {
+        builder = builder.max_size(1);
+    }
+    let pool = builder.build(manager)?;
+    init_schema(&pool)?;
+    Ok(pool)
+}
+
+fn init_schema(pool: &DbManager) -> Result<(), DbError> {
+    let conn = pool.get()?;
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS accounts (
+            id INTEGER PRIMARY KEY,
+            email TEXT NOT NULL UNIQUE,
+            password_hash TEXT,
+            verified INTEGER NOT NULL DEFAULT 0,
+            two_factor_enabled INTEGER NOT NULL DEFAULT 0,
+            created_at INTEGER NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS sessions (
+            token TEXT PRIMARY KEY,
+            account_id INTEGER NOT NULL,
+            expires_at INTEGER NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS account_verifications (
+            token TEXT PRIMARY KEY,
+            account_id INTEGER NOT NULL,
+            expires_at INTEGER NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS two_factor_codes (
+            account_id INTEGER PRIMARY KEY,
+            code TEXT NOT NULL,
+            attempts_remaining INTEGER NOT NULL,
+            expires_at INTEGER NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS oauth_links (
+            provider TEXT NOT NULL,
+            subject TEXT NOT NULL,
+            account_id INTEGER NOT NULL,
+            PRIMARY KEY (provider, subject)
+        );",
+    )?;
+    Ok(())
+}
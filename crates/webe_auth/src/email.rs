@@ -0,0 +1,65 @@
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Message, SmtpTransport, Transport};
+
+pub type EmailManager = SmtpTransport;
+
+#[derive(Debug)]
+pub enum EmailError {
+    Transport(lettre::transport::smtp::Error),
+    Build(lettre::error::Error),
+    Address(lettre::address::AddressError),
+}
+
+impl From<lettre::transport::smtp::Error> for EmailError {
+    fn from(err: lettre::transport::smtp::Error) -> EmailError {
+        EmailError::Transport(err)
+    }
+}
+
+impl From<lettre::error::Error> for EmailError {
+    fn from(err: lettre::error::Error) -> EmailError {
+        EmailError::Build(err)
+    }
+}
+
+impl From<lettre::address::AddressError> for EmailError {
+    fn from(err: lettre::address::AddressError) -> EmailError {
+        EmailError::Address(err)
+    }
+}
+
+impl std::fmt::Display for EmailError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EmailError::Transport(e) => write!(f, "smtp transport error: {}", e),
+            EmailError::Build(e) => write!(f, "failed to build email: {}", e),
+            EmailError::Address(e) => write!(f, "invalid email address: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for EmailError {}
+
+/// `lettre::SmtpTransport` already keeps an internal connection pool, so
+/// this just finishes configuring one against the given relay.
+pub fn create_smtp_pool(address: String, user: String, pass: String) -> Result<EmailManager, EmailError> {
+    let creds = Credentials::new(user, pass);
+    let transport = SmtpTransport::relay(&address)?.credentials(creds).build();
+    Ok(transport)
+}
+
+pub fn send_plain_text(
+    manager: &EmailManager,
+    from: &str,
+    to: &str,
+    subject: &str,
+    body: String,
+) -> Result<(), EmailError> {
+    let email = Message::builder()
+        .from(from.parse()?)
+        .to(to.parse()?)
+        .subject(subject)
+        .body(body)?;
+    manager.send(&email)?;
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use std::sync::Arc;
+
+use serde::Deserialize;
+use tokio::sync::Mutex;
+use webe_web::http::{Responder, WebeRequest, WebeResponse};
+
+use crate::{WebeAuth, WebeAuthError};
+
+#[derive(Deserialize)]
+struct CreateAccountRequest {
+    email: String,
+    password: String,
+}
+
+pub struct CreateAccountResponder {
+    auth_manager: Arc<Mutex<WebeAuth>>,
+}
+
+impl CreateAccountResponder {
+    pub fn new(auth_manager: Arc<Mutex<WebeAuth>>) -> CreateAccountResponder {
+        CreateAccountResponder { auth_manager }
+    }
+}
+
+#[async_trait::async_trait]
+impl Responder for CreateAccountResponder {
+    async fn respond(&self, req: WebeRequest) -> WebeResponse {
+        let body: CreateAccountRequest = match serde_json::from_slice(&req.body) {
+            Ok(body) => body,
+            Err(_) => return WebeResponse::status(400),
+        };
+        let auth_manager = self.auth_manager.lock().await;
+        match auth_manager.create_account(&body.email, &body.password) {
+            Ok(_) => WebeResponse::status(201),
+            Err(WebeAuthError::EmailTaken) => WebeResponse::status(409),
+            Err(_) => WebeResponse::status(500),
+        }
+    }
+}
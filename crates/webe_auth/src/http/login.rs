@@ -0,0 +1,125 @@
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+use webe_web::http::{Responder, WebeRequest, WebeResponse, ACCOUNT_ID_PARAM};
+
+use crate::{WebeAuth, WebeAuthError};
+
+#[derive(Deserialize)]
+struct LoginRequest {
+    email: String,
+    password: String,
+}
+
+#[derive(Serialize)]
+struct LoginResponse {
+    token: Option<String>,
+    two_factor_required: bool,
+    account_id: Option<u64>,
+}
+
+pub struct LoginResponder {
+    auth_manager: Arc<Mutex<WebeAuth>>,
+}
+
+impl LoginResponder {
+    pub fn new(auth_manager: Arc<Mutex<WebeAuth>>) -> LoginResponder {
+        LoginResponder { auth_manager }
+    }
+}
+
+#[async_trait::async_trait]
+impl Responder for LoginResponder {
+    async fn respond(&self, req: WebeRequest) -> WebeResponse {
+        let body: LoginRequest = match serde_json::from_slice(&req.body) {
+            Ok(body) => body,
+            Err(_) => return WebeResponse::status(400),
+        };
+        let auth_manager = self.auth_manager.lock().await;
+        match auth_manager.login(&body.email, &body.password) {
+            Ok(token) => WebeResponse::json(
+                200,
+                &LoginResponse { token: Some(token), two_factor_required: false, account_id: None },
+            ),
+            Err(WebeAuthError::TwoFactorRequired(account_id)) => WebeResponse::json(
+                200,
+                &LoginResponse { token: None, two_factor_required: true, account_id: Some(account_id) },
+            ),
+            Err(WebeAuthError::InvalidCredentials) => WebeResponse::status(401),
+            Err(_) => WebeResponse::status(500),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Login2FARequest {
+    account_id: u64,
+    code: String,
+}
+
+/// Completes a login that `LoginResponder` withheld a token for.
+pub struct Login2FAResponder {
+    auth_manager: Arc<Mutex<WebeAuth>>,
+}
+
+impl Login2FAResponder {
+    pub fn new(auth_manager: Arc<Mutex<WebeAuth>>) -> Login2FAResponder {
+        Login2FAResponder { auth_manager }
+    }
+}
+
+#[async_trait::async_trait]
+impl Responder for Login2FAResponder {
+    async fn respond(&self, req: WebeRequest) -> WebeResponse {
+        let body: Login2FARequest = match serde_json::from_slice(&req.body) {
+            Ok(body) => body,
+            Err(_) => return WebeResponse::status(400),
+        };
+        let auth_manager = self.auth_manager.lock().await;
+        match auth_manager.complete_login_2fa(body.account_id, &body.code) {
+            Ok(token) => WebeResponse::json(
+                200,
+                &LoginResponse { token: Some(token), two_factor_required: false, account_id: None },
+            ),
+            Err(WebeAuthError::TwoFactorAttemptsExceeded) => WebeResponse::status(429),
+            Err(WebeAuthError::TokenExpired) => WebeResponse::status(410),
+            Err(_) => WebeResponse::status(401),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Set2FARequest {
+    enabled: bool,
+}
+
+/// Secured route that lets a logged-in account turn email 2FA on or off.
+pub struct Set2FAResponder {
+    auth_manager: Arc<Mutex<WebeAuth>>,
+}
+
+impl Set2FAResponder {
+    pub fn new(auth_manager: Arc<Mutex<WebeAuth>>) -> Set2FAResponder {
+        Set2FAResponder { auth_manager }
+    }
+}
+
+#[async_trait::async_trait]
+impl Responder for Set2FAResponder {
+    async fn respond(&self, req: WebeRequest) -> WebeResponse {
+        let account_id = match req.params.get(ACCOUNT_ID_PARAM).and_then(|v| v.parse::<u64>().ok()) {
+            Some(id) => id,
+            None => return WebeResponse::status(401),
+        };
+        let body: Set2FARequest = match serde_json::from_slice(&req.body) {
+            Ok(body) => body,
+            Err(_) => return WebeResponse::status(400),
+        };
+        let auth_manager = self.auth_manager.lock().await;
+        match auth_manager.set_two_factor(account_id, body.enabled) {
+            Ok(()) => WebeResponse::status(200),
+            Err(_) => WebeResponse::status(500),
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use std::sync::Arc;
+
+use serde::Deserialize;
+use tokio::sync::Mutex;
+use webe_web::http::{Responder, WebeRequest, WebeResponse};
+
+use crate::WebeAuth;
+
+#[derive(Deserialize)]
+struct LogoutRequest {
+    token: String,
+}
+
+pub struct LogoutResponder {
+    auth_manager: Arc<Mutex<WebeAuth>>,
+}
+
+impl LogoutResponder {
+    pub fn new(auth_manager: Arc<Mutex<WebeAuth>>) -> LogoutResponder {
+        LogoutResponder { auth_manager }
+    }
+}
+
+#[async_trait::async_trait]
+impl Responder for LogoutResponder {
+    async fn respond(&self, req: WebeRequest) -> WebeResponse {
+        let body: LogoutRequest = match serde_json::from_slice(&req.body) {
+            Ok(body) => body,
+            Err(_) => return WebeResponse::status(400),
+        };
+        let auth_manager = self.auth_manager.lock().await;
+        match auth_manager.logout(&body.token) {
+            Ok(()) => WebeResponse::status(200),
+            Err(_) => WebeResponse::status(500),
+        }
+    }
+}
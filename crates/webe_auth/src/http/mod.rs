@@ -0,0 +1,6 @@
+pub mod create_account;
+pub mod login;
+pub mod logout;
+pub mod oauth;
+pub mod secure;
+pub mod verify_account;
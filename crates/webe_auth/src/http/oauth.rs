@@ -0,0 +1,79 @@
+use std::sync::Arc;
+
+use serde::Serialize;
+use tokio::sync::Mutex;
+use webe_web::http::{Responder, WebeRequest, WebeResponse};
+
+use crate::oauth::OAuthProvider;
+use crate::WebeAuth;
+
+fn redirect_uri(req: &WebeRequest) -> String {
+    let host = req.header("host").unwrap_or("localhost");
+    format!("http://{}/account/oauth/callback", host)
+}
+
+/// Kicks off the authorization-code flow by redirecting the browser to the
+/// provider's consent screen.
+pub struct OAuthAuthorizeResponder {
+    provider: OAuthProvider,
+}
+
+impl OAuthAuthorizeResponder {
+    pub fn new(provider: OAuthProvider) -> OAuthAuthorizeResponder {
+        OAuthAuthorizeResponder { provider }
+    }
+}
+
+#[async_trait::async_trait]
+impl Responder for OAuthAuthorizeResponder {
+    async fn respond(&self, req: WebeRequest) -> WebeResponse {
+        let state = req.query.get("state").cloned().unwrap_or_default();
+        let redirect_uri = redirect_uri(&req);
+        let location = self.provider.authorize_url(&redirect_uri, &state);
+        WebeResponse::status(302).header("location", location)
+    }
+}
+
+#[derive(Serialize)]
+struct OAuthLoginResponse {
+    token: String,
+}
+
+/// Exchanges the `code` query param for the provider's verified email, then
+/// provisions/links the local account and mints the same session token
+/// `secure::SecureResponder` validates everywhere else.
+pub struct OAuthCallbackResponder {
+    auth_manager: Arc<Mutex<WebeAuth>>,
+    provider: OAuthProvider,
+}
+
+impl OAuthCallbackResponder {
+    pub fn new(auth_manager: Arc<Mutex<WebeAuth>>, provider: OAuthProvider) -> OAuthCallbackResponder {
+        OAuthCallbackResponder { auth_manager, provider }
+    }
+}
+
+#[async_trait::async_trait]
+impl Responder for OAuthCallbackResponder {
+    async fn respond(&self, req: WebeRequest) -> WebeResponse {
+        let code = match req.query.get("code") {
+            Some(code) => code,
+            None => return WebeResponse::status(400),
+        };
+        let redirect_uri = redirect_uri(&req);
+        let identity = match self.provider.exchange_code(code, &redirect_uri).await {
+            Ok(identity) => identity,
+            Err(_) => return WebeResponse::status(502),
+        };
+        let auth_manager = self.auth_manager.lock().await;
+        let account_id = match auth_manager.find_or_create_oauth_account("oauth", &identity.subject, &identity.email)
+        {
+            Ok(account_id) => account_id,
+            Err(_) => return WebeResponse::status(500),
+        };
+        match auth_manager.issue_session(account_id) {
+            Ok(token) => WebeResponse::json(200, &OAuthLoginResponse { token }),
+            Err(_) => WebeResponse::status(500),
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use std::sync::Arc;
+
+use tokio::sync::Mutex;
+use webe_web::http::{Responder, WebeRequest, WebeResponse, ACCOUNT_ID_PARAM};
+
+use crate::WebeAuth;
+
+/// Validates the `x-webe-token` session header before delegating to `inner`,
+/// so every secured route gets the same auth check without repeating it.
+pub struct SecureResponder<R: Responder> {
+    auth_manager: Arc<Mutex<WebeAuth>>,
+    inner: R,
+}
+
+impl<R: Responder> SecureResponder<R> {
+    pub fn new(auth_manager: Arc<Mutex<WebeAuth>>, inner: R) -> SecureResponder<R> {
+        SecureResponder { auth_manager, inner }
+    }
+}
+
+#[async_trait::async_trait]
+impl<R: Responder + Send + Sync> Responder for SecureResponder<R> {
+    async fn respond(&self, mut req: WebeRequest) -> WebeResponse {
+        let token = match req.header("x-webe-token") {
+            Some(token) => token.to_string(),
+            None => return WebeResponse::status(401),
+        };
+        let auth_manager = self.auth_manager.lock().await;
+        match auth_manager.validate_session(&token) {
+            Ok(account_id) => {
+                drop(auth_manager);
+                req.params.insert(ACCOUNT_ID_PARAM.to_string(), account_id.to_string());
+                self.inner.respond(req).await
+            }
+            Err(_) => WebeResponse::status(401),
+        }
+    }
+}
@@ -0,0 +1,438 @@
+pub mod db;
+pub mod email;
+pub mod http;
+pub mod oauth;
+
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rand::Rng;
+use sha2::{Digest, Sha256};
+use webe_id::WebeIDFactory;
+
+pub const SESSION_TTL_SECONDS: i64 = 60 * 60 * 24 * 7; // 1 week
+pub const VERIFICATION_TTL_SECONDS: i64 = 60 * 60 * 24; // 1 day
+pub const UNVERIFIED_ACCOUNT_TTL_SECONDS: i64 = 60 * 60 * 24 * 7; // 1 week
+pub const TWO_FACTOR_CODE_TTL_SECONDS: i64 = 60 * 10; // 10 minutes
+pub const TWO_FACTOR_MAX_ATTEMPTS: i64 = 5;
+
+#[derive(Debug)]
+pub enum WebeAuthError {
+    Db(db::DbError),
+    Email(email::EmailError),
+    Id(webe_id::WebeIDError),
+    EmailTaken,
+    InvalidCredentials,
+    AccountNotFound,
+    TokenInvalid,
+    TokenExpired,
+    TwoFactorRequired(u64),
+    TwoFactorCodeInvalid,
+    TwoFactorAttemptsExceeded,
+}
+
+impl From<db::DbError> for WebeAuthError {
+    fn from(err: db::DbError) -> WebeAuthError {
+        WebeAuthError::Db(err)
+    }
+}
+
+impl From<rusqlite::Error> for WebeAuthError {
+    fn from(err: rusqlite::Error) -> WebeAuthError {
+        WebeAuthError::Db(db::DbError::from(err))
+    }
+}
+
+impl From<r2d2::Error> for WebeAuthError {
+    fn from(err: r2d2::Error) -> WebeAuthError {
+        WebeAuthError::Db(db::DbError::from(err))
+    }
+}
+
+impl From<email::EmailError> for WebeAuthError {
+    fn from(err: email::EmailError) -> WebeAuthError {
+        WebeAuthError::Email(err)
+    }
+}
+
+impl From<webe_id::WebeIDError> for WebeAuthError {
+    fn from(err: webe_id::WebeIDError) -> WebeAuthError {
+        WebeAuthError::Id(err)
+    }
+}
+
+impl std::fmt::Display for WebeAuthError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl std::error::Error for WebeAuthError {}
+
+fn now() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).expect("system clock before epoch").as_secs() as i64
+}
+
+fn hash_password(password: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(password.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+fn new_token() -> String {
+    let bytes: [u8; 32] = rand::rng().random();
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn new_two_factor_code() -> String {
+    let code: u32 = rand::rng().random_range(0..1_000_000);
+    format!("{:06}", code)
+}
+
+pub struct WebeAuth {
+    pub db_manager: db::DbManager,
+    pub email_manager: email::EmailManager,
+    pub id_factory: Arc<StdMutex<WebeIDFactory>>,
+}
+
+impl WebeAuth {
+    fn next_id(&self) -> Result<u64, WebeAuthError> {
+        let mut factory = self.id_factory.lock().expect("id factory mutex poisoned");
+        Ok(factory.next()?)
+    }
+
+    pub fn create_account(&self, email_address: &str, password: &str) -> Result<String, WebeAuthError> {
+        let conn = self.db_manager.get()?;
+        let existing: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM accounts WHERE email = ?1",
+            [email_address],
+            |row| row.get(0),
+        )?;
+        if existing > 0 {
+            return Err(WebeAuthError::EmailTaken);
+        }
+        let account_id = self.next_id()?;
+        conn.execute(
+            "INSERT INTO accounts (id, email, password_hash, verified, two_factor_enabled, created_at) VALUES (?1, ?2, ?3, 0, 0, ?4)",
+            rusqlite::params![account_id as i64, email_address, hash_password(password), now()],
+        )?;
+        let token = new_token();
+        conn.execute(
+            "INSERT INTO account_verifications (token, account_id, expires_at) VALUES (?1, ?2, ?3)",
+            rusqlite::params![token, account_id as i64, now() + VERIFICATION_TTL_SECONDS],
+        )?;
+        let _ = email::send_plain_text(
+            &self.email_manager,
+            "no-reply@flashcard-server",
+            email_address,
+            "Verify your account",
+            format!("Your verification code is: {}", token),
+        );
+        Ok(token)
+    }
+
+    pub fn verify_account(&self, token: &str) -> Result<(), WebeAuthError> {
+        let conn = self.db_manager.get()?;
+        let row: Option<(i64, i64)> = conn
+            .query_row(
+                "SELECT account_id, expires_at FROM account_verifications WHERE token = ?1",
+                [token],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .ok();
+        let (account_id, expires_at) = row.ok_or(WebeAuthError::TokenInvalid)?;
+        if expires_at < now() {
+            return Err(WebeAuthError::TokenExpired);
+        }
+        conn.execute("UPDATE accounts SET verified = 1 WHERE id = ?1", [account_id])?;
+        conn.execute("DELETE FROM account_verifications WHERE token = ?1", [token])?;
+        Ok(())
+    }
+
+    /// Returns `Ok(token)` when no second factor is required, or
+    /// `Err(TwoFactorRequired(account_id))` when the caller must follow up
+    /// with `complete_login_2fa`.
+    pub fn login(&self, email_address: &str, password: &str) -> Result<String, WebeAuthError> {
+        let conn = self.db_manager.get()?;
+        let row: Option<(i64, String, bool)> = conn
+            .query_row(
+                "SELECT id, password_hash, two_factor_enabled FROM accounts WHERE email = ?1",
+                [email_address],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get::<_, i64>(2)? != 0)),
+            )
+            .ok();
+        let (account_id, password_hash, two_factor_enabled) = row.ok_or(WebeAuthError::InvalidCredentials)?;
+        let account_id = account_id as u64;
+        drop(conn);
+        if password_hash != hash_password(password) {
+            return Err(WebeAuthError::InvalidCredentials);
+        }
+        if two_factor_enabled {
+            self.issue_two_factor_code(account_id, email_address)?;
+            return Err(WebeAuthError::TwoFactorRequired(account_id));
+        }
+        self.issue_session(account_id)
+    }
+
+    fn issue_two_factor_code(&self, account_id: u64, email_address: &str) -> Result<(), WebeAuthError> {
+        let conn = self.db_manager.get()?;
+        let code = new_two_factor_code();
+        conn.execute(
+            "INSERT INTO two_factor_codes (account_id, code, attempts_remaining, expires_at)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(account_id) DO UPDATE SET code = excluded.code, attempts_remaining = excluded.attempts_remaining, expires_at = excluded.expires_at",
+            rusqlite::params![account_id as i64, code, TWO_FACTOR_MAX_ATTEMPTS, now() + TWO_FACTOR_CODE_TTL_SECONDS],
+        )?;
+        let _ = email::send_plain_text(
+            &self.email_manager,
+            "no-reply@flashcard-server",
+            email_address,
+            "Your login code",
+            format!("Your login code is: {}", code),
+        );
+        Ok(())
+    }
+
+    pub fn complete_login_2fa(&self, account_id: u64, code: &str) -> Result<String, WebeAuthError> {
+        let conn = self.db_manager.get()?;
+        let account_id_param = account_id as i64;
+        let row: Option<(String, i64, i64)> = conn
+            .query_row(
+                "SELECT code, attempts_remaining, expires_at FROM two_factor_codes WHERE account_id = ?1",
+                [account_id_param],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .ok();
+        let (expected_code, attempts_remaining, expires_at) = row.ok_or(WebeAuthError::TwoFactorCodeInvalid)?;
+        if attempts_remaining <= 0 {
+            conn.execute("DELETE FROM two_factor_codes WHERE account_id = ?1", [account_id_param])?;
+            return Err(WebeAuthError::TwoFactorAttemptsExceeded);
+        }
+        if expires_at < now() {
+            conn.execute("DELETE FROM two_factor_codes WHERE account_id = ?1", [account_id_param])?;
+            return Err(WebeAuthError::TokenExpired);
+        }
+        if code != expected_code {
+            conn.execute(
+                "UPDATE two_factor_codes SET attempts_remaining = attempts_remaining - 1 WHERE account_id = ?1",
+                [account_id_param],
+            )?;
+            return Err(WebeAuthError::TwoFactorCodeInvalid);
+        }
+        conn.execute("DELETE FROM two_factor_codes WHERE account_id = ?1", [account_id_param])?;
+        drop(conn);
+        self.issue_session(account_id)
+    }
+
+    pub fn set_two_factor(&self, account_id: u64, enabled: bool) -> Result<(), WebeAuthError> {
+        let conn = self.db_manager.get()?;
+        conn.execute(
+            "UPDATE accounts SET two_factor_enabled = ?1 WHERE id = ?2",
+            rusqlite::params![enabled as i64, account_id as i64],
+        )?;
+        Ok(())
+    }
+
+    pub(crate) fn issue_session(&self, account_id: u64) -> Result<String, WebeAuthError> {
+        let conn = self.db_manager.get()?;
+        let token = new_token();
+        conn.execute(
+            "INSERT INTO sessions (token, account_id, expires_at) VALUES (?1, ?2, ?3)",
+            rusqlite::params![token, account_id as i64, now() + SESSION_TTL_SECONDS],
+        )?;
+        Ok(token)
+    }
+
+    pub fn logout(&self, token: &str) -> Result<(), WebeAuthError> {
+        let conn = self.db_manager.get()?;
+        conn.execute("DELETE FROM sessions WHERE token = ?1", [token])?;
+        Ok(())
+    }
+
+    /// Validates a session token the way `http::secure::SecureResponder` needs to.
+    pub fn validate_session(&self, token: &str) -> Result<u64, WebeAuthError> {
+        let conn = self.db_manager.get()?;
+        let row: Option<(i64, i64)> = conn
+            .query_row(
+                "SELECT account_id, expires_at FROM sessions WHERE token = ?1",
+                [token],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .ok();
+        let (account_id, expires_at) = row.ok_or(WebeAuthError::TokenInvalid)?;
+        if expires_at < now() {
+            return Err(WebeAuthError::TokenExpired);
+        }
+        Ok(account_id as u64)
+    }
+
+    /// Finds the account linked to an OAuth identity, or auto-creates one
+    /// (pre-verified, since the provider already verified the email).
+    pub fn find_or_create_oauth_account(
+        &self,
+        provider: &str,
+        subject: &str,
+        verified_email: &str,
+    ) -> Result<u64, WebeAuthError> {
+        let conn = self.db_manager.get()?;
+        let linked: Option<i64> = conn
+            .query_row(
+                "SELECT account_id FROM oauth_links WHERE provider = ?1 AND subject = ?2",
+                [provider, subject],
+                |row| row.get(0),
+            )
+            .ok();
+        if let Some(account_id) = linked {
+            return Ok(account_id as u64);
+        }
+        let existing: Option<i64> = conn
+            .query_row("SELECT id FROM accounts WHERE email = ?1", [verified_email], |row| row.get(0))
+            .ok();
+        let account_id = match existing {
+            Some(account_id) => account_id as u64,
+            None => {
+                let account_id = self.next_id()?;
+                conn.execute(
+                    "INSERT INTO accounts (id, email, password_hash, verified, two_factor_enabled, created_at) VALUES (?1, ?2, NULL, 1, 0, ?3)",
+                    rusqlite::params![account_id as i64, verified_email, now()],
+                )?;
+                account_id
+            }
+        };
+        conn.execute(
+            "INSERT OR IGNORE INTO oauth_links (provider, subject, account_id) VALUES (?1, ?2, ?3)",
+            rusqlite::params![provider, subject, account_id as i64],
+        )?;
+        Ok(account_id)
+    }
+
+    pub fn purge_expired_sessions(&self) -> Result<usize, WebeAuthError> {
+        let conn = self.db_manager.get()?;
+        Ok(conn.execute("DELETE FROM sessions WHERE expires_at < ?1", [now()])?)
+    }
+
+    pub fn purge_expired_verifications(&self) -> Result<usize, WebeAuthError> {
+        let conn = self.db_manager.get()?;
+        Ok(conn.execute("DELETE FROM account_verifications WHERE expires_at < ?1", [now()])?)
+    }
+
+    pub fn purge_stale_unverified_accounts(&self) -> Result<usize, WebeAuthError> {
+        let conn = self.db_manager.get()?;
+        Ok(conn.execute(
+            "DELETE FROM accounts WHERE verified = 0 AND created_at < ?1",
+            [now() - UNVERIFIED_ACCOUNT_TTL_SECONDS],
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn test_auth() -> WebeAuth {
+        let db_manager = db::new_manager(":memory:".to_string()).expect("failed to create in-memory db");
+        let email_manager = email::create_smtp_pool(
+            "localhost:2525".to_string(),
+            "user".to_string(),
+            "pass".to_string(),
+        )
+        .expect("failed to create smtp transport");
+        let epoch = UNIX_EPOCH.checked_add(Duration::from_millis(1546300800000)).unwrap();
+        let id_factory = Arc::new(StdMutex::new(WebeIDFactory::new(epoch, 0u8).unwrap()));
+        WebeAuth { db_manager, email_manager, id_factory }
+    }
+
+    #[test]
+    fn create_and_verify_account() {
+        let auth = test_auth();
+        let token = auth.create_account("user@example.com", "hunter2").unwrap();
+        assert!(auth.login("user@example.com", "hunter2").is_ok());
+        auth.verify_account(&token).unwrap();
+    }
+
+    #[test]
+    fn login_rejects_wrong_password() {
+        let auth = test_auth();
+        auth.create_account("user@example.com", "hunter2").unwrap();
+        assert!(matches!(auth.login("user@example.com", "wrong"), Err(WebeAuthError::InvalidCredentials)));
+    }
+
+    #[test]
+    fn two_factor_gates_session_issuance() {
+        let auth = test_auth();
+        auth.create_account("user@example.com", "hunter2").unwrap();
+        let account_id = auth.validate_session(&auth.login("user@example.com", "hunter2").unwrap()).unwrap();
+        auth.set_two_factor(account_id, true).unwrap();
+        let err = auth.login("user@example.com", "hunter2").unwrap_err();
+        let pending_id = match err {
+            WebeAuthError::TwoFactorRequired(id) => id,
+            other => panic!("expected TwoFactorRequired, got {:?}", other),
+        };
+        let code: String = auth
+            .db_manager
+            .get()
+            .unwrap()
+            .query_row("SELECT code FROM two_factor_codes WHERE account_id = ?1", [pending_id as i64], |row| row.get(0))
+            .unwrap();
+        assert!(matches!(auth.complete_login_2fa(pending_id, "000000"), Err(WebeAuthError::TwoFactorCodeInvalid)) || code != "000000");
+        let token = auth.complete_login_2fa(pending_id, &code).unwrap();
+        assert_eq!(auth.validate_session(&token).unwrap(), pending_id);
+    }
+
+    #[test]
+    fn two_factor_locks_out_after_max_attempts() {
+        let auth = test_auth();
+        auth.create_account("user@example.com", "hunter2").unwrap();
+        let account_id = auth.validate_session(&auth.login("user@example.com", "hunter2").unwrap()).unwrap();
+        auth.set_two_factor(account_id, true).unwrap();
+        let pending_id = match auth.login("user@example.com", "hunter2").unwrap_err() {
+            WebeAuthError::TwoFactorRequired(id) => id,
+            other => panic!("expected TwoFactorRequired, got {:?}", other),
+        };
+        for _ in 0..TWO_FACTOR_MAX_ATTEMPTS {
+            assert!(matches!(
+                auth.complete_login_2fa(pending_id, "wrong"),
+                Err(WebeAuthError::TwoFactorCodeInvalid)
+            ));
+        }
+        assert!(matches!(
+            auth.complete_login_2fa(pending_id, "wrong"),
+            Err(WebeAuthError::TwoFactorAttemptsExceeded)
+        ));
+    }
+
+    #[test]
+    fn purge_removes_expired_sessions() {
+        let auth = test_auth();
+        auth.create_account("user@example.com", "hunter2").unwrap();
+        let token = auth.login("user@example.com", "hunter2").unwrap();
+        auth.db_manager
+            .get()
+            .unwrap()
+            .execute("UPDATE sessions SET expires_at = 0 WHERE token = ?1", [&token])
+            .unwrap();
+        let purged = auth.purge_expired_sessions().unwrap();
+        assert_eq!(purged, 1);
+        assert!(matches!(auth.validate_session(&token), Err(WebeAuthError::TokenInvalid)));
+    }
+
+    #[test]
+    fn purge_removes_expired_verifications_and_stale_unverified_accounts() {
+        let auth = test_auth();
+        let token = auth.create_account("user@example.com", "hunter2").unwrap();
+        let conn = auth.db_manager.get().unwrap();
+        conn.execute("UPDATE account_verifications SET expires_at = 0 WHERE token = ?1", [&token]).unwrap();
+        conn.execute("UPDATE accounts SET created_at = 0 WHERE email = 'user@example.com'", []).unwrap();
+        drop(conn);
+
+        assert_eq!(auth.purge_expired_verifications().unwrap(), 1);
+        assert!(matches!(auth.verify_account(&token), Err(WebeAuthError::TokenInvalid)));
+
+        assert_eq!(auth.purge_stale_unverified_accounts().unwrap(), 1);
+        assert!(matches!(
+            auth.login("user@example.com", "hunter2"),
+            Err(WebeAuthError::InvalidCredentials)
+        ));
+    }
+}
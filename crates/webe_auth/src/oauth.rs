@@ -0,0 +1,127 @@
+use serde::Deserialize;
+
+/// Static config for a single "sign in with X" provider, loaded from `.env`.
+#[derive(Clone)]
+pub struct OAuthProvider {
+    pub client_id: String,
+    pub client_secret: String,
+    pub auth_url: String,
+    pub token_url: String,
+    pub userinfo_url: String,
+    pub scopes: String,
+}
+
+#[derive(Debug)]
+pub enum OAuthError {
+    Http(reqwest::Error),
+    NoEmail,
+}
+
+impl From<reqwest::Error> for OAuthError {
+    fn from(err: reqwest::Error) -> OAuthError {
+        OAuthError::Http(err)
+    }
+}
+
+impl std::fmt::Display for OAuthError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OAuthError::Http(e) => write!(f, "oauth http error: {}", e),
+            OAuthError::NoEmail => write!(f, "oauth provider did not return a verified email"),
+        }
+    }
+}
+
+impl std::error::Error for OAuthError {}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+}
+
+#[derive(Deserialize)]
+struct UserInfo {
+    sub: Option<String>,
+    email: Option<String>,
+    email_verified: Option<bool>,
+}
+
+pub struct VerifiedIdentity {
+    pub subject: String,
+    pub email: String,
+}
+
+impl OAuthProvider {
+    pub fn authorize_url(&self, redirect_uri: &str, state: &str) -> String {
+        format!(
+            "{}?client_id={}&redirect_uri={}&scope={}&response_type=code&state={}",
+            self.auth_url,
+            urlencoding::encode(&self.client_id),
+            urlencoding::encode(redirect_uri),
+            urlencoding::encode(&self.scopes),
+            urlencoding::encode(state),
+        )
+    }
+
+    /// Exchanges an authorization `code` for the provider's verified email,
+    /// per the standard authorization-code flow.
+    pub async fn exchange_code(&self, code: &str, redirect_uri: &str) -> Result<VerifiedIdentity, OAuthError> {
+        let client = reqwest::Client::new();
+        let token_response: TokenResponse = client
+            .post(&self.token_url)
+            .form(&[
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("redirect_uri", redirect_uri),
+                ("client_id", &self.client_id),
+                ("client_secret", &self.client_secret),
+            ])
+            .send()
+            .await?
+            .json()
+            .await?;
+
+        let userinfo: UserInfo = client
+            .get(&self.userinfo_url)
+            .bearer_auth(&token_response.access_token)
+            .send()
+            .await?
+            .json()
+            .await?;
+
+        match (userinfo.email, userinfo.email_verified.unwrap_or(true)) {
+            (Some(email), true) => {
+                Ok(VerifiedIdentity { subject: userinfo.sub.unwrap_or_else(|| email.clone()), email })
+            }
+            _ => Err(OAuthError::NoEmail),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_provider() -> OAuthProvider {
+        OAuthProvider {
+            client_id: "client id with spaces".to_string(),
+            client_secret: "secret".to_string(),
+            auth_url: "https://provider.example/authorize".to_string(),
+            token_url: "https://provider.example/token".to_string(),
+            userinfo_url: "https://provider.example/userinfo".to_string(),
+            scopes: "openid email".to_string(),
+        }
+    }
+
+    #[test]
+    fn authorize_url_percent_encodes_every_query_param() {
+        let provider = test_provider();
+        let url = provider.authorize_url("https://app.example/callback?x=1", "csrf-token");
+        assert_eq!(
+            url,
+            "https://provider.example/authorize?client_id=client%20id%20with%20spaces\
+             &redirect_uri=https%3A%2F%2Fapp.example%2Fcallback%3Fx%3D1\
+             &scope=openid%20email&response_type=code&state=csrf-token"
+        );
+    }
+}
@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+
+/// Reserved request-param key that `secure::SecureResponder` (in `webe_auth`)
+/// fills in with the authenticated account id once a token has been
+/// validated, so downstream responders never have to re-parse the token.
+pub const ACCOUNT_ID_PARAM: &str = "__account_id__";
+
+/// A framework-level request, decoupled from whatever HTTP library
+/// actually terminates the connection (see `server.rs`).
+pub struct WebeRequest {
+    pub method: String,
+    pub path: String,
+    pub params: HashMap<String, String>,
+    pub query: HashMap<String, String>,
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+    /// Present only when the client asked to switch protocols (e.g. a
+    /// WebSocket handshake); `ws::WebSocketResponder` awaits this after
+    /// returning its 101 response to take over the raw connection.
+    pub upgrade: Option<hyper::upgrade::OnUpgrade>,
+}
+
+impl WebeRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(&name.to_ascii_lowercase()).map(|v| v.as_str())
+    }
+
+    pub fn body_str(&self) -> Result<&str, std::str::Utf8Error> {
+        std::str::from_utf8(&self.body)
+    }
+}
+
+pub struct WebeResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl WebeResponse {
+    pub fn status(status: u16) -> WebeResponse {
+        WebeResponse { status, headers: Vec::new(), body: Vec::new() }
+    }
+
+    pub fn with_body(status: u16, body: impl Into<Vec<u8>>) -> WebeResponse {
+        WebeResponse { status, headers: Vec::new(), body: body.into() }
+    }
+
+    pub fn json(status: u16, value: &impl serde::Serialize) -> WebeResponse {
+        let body = serde_json::to_vec(value).unwrap_or_default();
+        WebeResponse {
+            status,
+            headers: vec![("content-type".to_string(), "application/json".to_string())],
+            body,
+        }
+    }
+
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> WebeResponse {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+}
+
+/// Implemented by every endpoint handler the server dispatches to.
+#[async_trait::async_trait]
+pub trait Responder: Send + Sync {
+    async fn respond(&self, req: WebeRequest) -> WebeResponse;
+}
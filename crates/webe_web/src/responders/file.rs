@@ -0,0 +1,55 @@
+use std::path::{Component, Path, PathBuf};
+
+use crate::http::{Responder, WebeRequest, WebeResponse};
+
+#[derive(Debug)]
+pub enum FileResponderError {
+    BadRoot,
+}
+
+/// Serves a static file out of `root`, keyed off the `<path>` route param.
+pub struct FileResponder {
+    root: PathBuf,
+    param: String,
+}
+
+impl FileResponder {
+    pub fn new(root: String, param: String) -> Result<FileResponder, FileResponderError> {
+        let root = PathBuf::from(root);
+        if root.as_os_str().is_empty() {
+            return Err(FileResponderError::BadRoot);
+        }
+        Ok(FileResponder { root, param })
+    }
+}
+
+/// Rejects `..`/absolute segments so a `<path>` param can't escape `root`.
+fn safe_join(root: &Path, requested: &str) -> Option<PathBuf> {
+    let mut resolved = root.to_path_buf();
+    for component in PathBuf::from(requested).components() {
+        match component {
+            Component::Normal(part) => resolved.push(part),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    Some(resolved)
+}
+
+#[async_trait::async_trait]
+impl Responder for FileResponder {
+    async fn respond(&self, req: WebeRequest) -> WebeResponse {
+        let requested = match req.params.get(&self.param) {
+            Some(value) => value,
+            None => return WebeResponse::status(400),
+        };
+        let path = match safe_join(&self.root, requested) {
+            Some(path) => path,
+            None => return WebeResponse::status(400),
+        };
+        match tokio::fs::read(&path).await {
+            Ok(body) => WebeResponse::with_body(200, body),
+            Err(_) => WebeResponse::status(404),
+        }
+    }
+}
@@ -0,0 +1,4 @@
+pub mod file;
+pub mod options;
+pub mod spa;
+pub mod ws;
@@ -0,0 +1,24 @@
+use crate::http::{Responder, WebeRequest, WebeResponse};
+
+/// Answers CORS preflight `OPTIONS` requests.
+pub struct OptionsResponder {
+    allow_origin: String,
+    allow_methods: String,
+    allow_headers: String,
+}
+
+impl OptionsResponder {
+    pub fn new(allow_origin: String, allow_methods: String, allow_headers: String) -> OptionsResponder {
+        OptionsResponder { allow_origin, allow_methods, allow_headers }
+    }
+}
+
+#[async_trait::async_trait]
+impl Responder for OptionsResponder {
+    async fn respond(&self, _req: WebeRequest) -> WebeResponse {
+        WebeResponse::status(204)
+            .header("access-control-allow-origin", self.allow_origin.clone())
+            .header("access-control-allow-methods", self.allow_methods.clone())
+            .header("access-control-allow-headers", self.allow_headers.clone())
+    }
+}
@@ -0,0 +1,33 @@
+use std::path::PathBuf;
+
+use crate::http::{Responder, WebeRequest, WebeResponse};
+
+#[derive(Debug)]
+pub enum SPAResponderError {
+    BadRoot,
+}
+
+/// Wildcard fallback that always serves the SPA's `index.html`, letting the
+/// client-side router handle the actual path.
+pub struct SPAResponder {
+    index_path: PathBuf,
+}
+
+impl SPAResponder {
+    pub fn new(root: String, index_path: String) -> Result<SPAResponder, SPAResponderError> {
+        if root.is_empty() {
+            return Err(SPAResponderError::BadRoot);
+        }
+        Ok(SPAResponder { index_path: PathBuf::from(root).join(index_path) })
+    }
+}
+
+#[async_trait::async_trait]
+impl Responder for SPAResponder {
+    async fn respond(&self, _req: WebeRequest) -> WebeResponse {
+        match tokio::fs::read(&self.index_path).await {
+            Ok(body) => WebeResponse::with_body(200, body).header("content-type", "text/html"),
+            Err(_) => WebeResponse::status(404),
+        }
+    }
+}
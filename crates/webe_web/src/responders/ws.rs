@@ -0,0 +1,98 @@
+use std::sync::Arc;
+
+use base64::Engine;
+use futures_util::{SinkExt, StreamExt};
+use sha1::{Digest, Sha1};
+use tokio::sync::{mpsc, Mutex};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::WebSocketStream;
+
+use crate::http::{Responder, WebeRequest, WebeResponse, ACCOUNT_ID_PARAM};
+
+const WEBSOCKET_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+fn accept_key(client_key: &str) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(client_key.as_bytes());
+    hasher.update(WEBSOCKET_GUID.as_bytes());
+    base64::engine::general_purpose::STANDARD.encode(hasher.finalize())
+}
+
+/// Anything that can hand out a live feed of JSON event strings for a given
+/// account. `lib_flashcard::FlashManager` implements this over its
+/// deck/card broadcast registry.
+#[async_trait::async_trait]
+pub trait BroadcastSource: Send + Sync {
+    async fn subscribe(&self, account_id: u64) -> mpsc::Receiver<String>;
+}
+
+/// Upgrades an already-authenticated request (see `ACCOUNT_ID_PARAM`) to a
+/// WebSocket and streams that account's deck/card change events to it.
+pub struct WebSocketResponder<T: BroadcastSource> {
+    source: Arc<Mutex<T>>,
+}
+
+impl<T: BroadcastSource> WebSocketResponder<T> {
+    pub fn new(source: Arc<Mutex<T>>) -> WebSocketResponder<T> {
+        WebSocketResponder { source }
+    }
+}
+
+#[async_trait::async_trait]
+impl<T: BroadcastSource + Send + Sync + 'static> Responder for WebSocketResponder<T> {
+    async fn respond(&self, req: WebeRequest) -> WebeResponse {
+        let account_id = match req.params.get(ACCOUNT_ID_PARAM).and_then(|v| v.parse::<u64>().ok()) {
+            Some(id) => id,
+            None => return WebeResponse::status(401),
+        };
+        let client_key = match req.header("sec-websocket-key") {
+            Some(key) => key.to_string(),
+            None => return WebeResponse::status(400),
+        };
+        let upgrade = match req.upgrade {
+            Some(upgrade) => upgrade,
+            None => return WebeResponse::status(400),
+        };
+        let source = self.source.clone();
+        tokio::spawn(async move {
+            let upgraded = match upgrade.await {
+                Ok(upgraded) => upgraded,
+                Err(e) => {
+                    eprintln!("WebSocket upgrade failed: {:?}", e);
+                    return;
+                }
+            };
+            let io = hyper_util::rt::TokioIo::new(upgraded);
+            let ws_stream =
+                WebSocketStream::from_raw_socket(io, tokio_tungstenite::tungstenite::protocol::Role::Server, None)
+                    .await;
+            let (mut sink, mut stream) = ws_stream.split();
+            let mut events = source.lock().await.subscribe(account_id).await;
+            loop {
+                tokio::select! {
+                    event = events.recv() => {
+                        match event {
+                            Some(event) => {
+                                if sink.send(Message::Text(event.into())).await.is_err() {
+                                    break;
+                                }
+                            }
+                            None => break,
+                        }
+                    }
+                    incoming = stream.next() => {
+                        match incoming {
+                            Some(Ok(Message::Close(_))) | None => break,
+                            Some(Err(_)) => break,
+                            _ => {}
+                        }
+                    }
+                }
+            }
+        });
+        WebeResponse::status(101)
+            .header("upgrade", "websocket")
+            .header("connection", "Upgrade")
+            .header("sec-websocket-accept", accept_key(&client_key))
+    }
+}
@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
+use std::sync::Arc;
+
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full};
+use hyper::body::Incoming;
+use hyper::service::service_fn;
+use hyper::{Request, Response};
+use hyper_util::rt::TokioIo;
+use tokio::net::TcpListener;
+
+use crate::http::{Responder, WebeRequest, WebeResponse};
+
+#[derive(Debug)]
+pub enum ServerError {
+    Bind(std::io::Error),
+    Accept(std::io::Error),
+}
+
+impl std::fmt::Display for ServerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ServerError::Bind(e) => write!(f, "failed to bind web server: {}", e),
+            ServerError::Accept(e) => write!(f, "failed to accept connection: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ServerError {}
+
+enum Segment {
+    Literal(String),
+    Param(String),
+}
+
+/// A single `METHOD /path/<param>` route pattern.
+pub struct Route {
+    method: String,
+    segments: Vec<Segment>,
+}
+
+impl Route {
+    pub fn new(method: &str, pattern: &str) -> Route {
+        let segments = pattern
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .map(|s| {
+                // params are keyed by the whole `<name>` segment, brackets included
+                if s.starts_with('<') && s.ends_with('>') {
+                    Segment::Param(s.to_string())
+                } else {
+                    Segment::Literal(s.to_string())
+                }
+            })
+            .collect();
+        Route { method: method.to_ascii_uppercase(), segments }
+    }
+
+    fn matches(&self, method: &str, path: &str) -> Option<HashMap<String, String>> {
+        if self.method != method {
+            return None;
+        }
+        let path_segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        if path_segments.len() != self.segments.len() {
+            return None;
+        }
+        let mut params = HashMap::new();
+        for (segment, value) in self.segments.iter().zip(path_segments.iter()) {
+            match segment {
+                Segment::Literal(literal) => {
+                    if literal != value {
+                        return None;
+                    }
+                }
+                Segment::Param(name) => {
+                    params.insert(name.clone(), value.to_string());
+                }
+            }
+        }
+        Some(params)
+    }
+}
+
+/// Registered routes, matched in registration order (first match wins,
+/// same as the existing `/<wildcard>` SPA fallback relies on).
+#[derive(Default)]
+pub struct RouteMap {
+    routes: Vec<(Route, Box<dyn Responder>)>,
+}
+
+impl RouteMap {
+    pub fn new() -> RouteMap {
+        RouteMap { routes: Vec::new() }
+    }
+
+    pub fn add_route<R: Responder + 'static>(&mut self, route: Route, responder: R) {
+        self.routes.push((route, Box::new(responder)));
+    }
+
+    async fn dispatch(&self, req: WebeRequest) -> WebeResponse {
+        for (route, responder) in &self.routes {
+            if let Some(params) = route.matches(&req.method, &req.path) {
+                let mut req = req;
+                req.params = params;
+                return responder.respond(req).await;
+            }
+        }
+        WebeResponse::status(404)
+    }
+}
+
+pub struct Server {
+    listener: TcpListener,
+}
+
+impl Server {
+    pub async fn new(ip: &Ipv4Addr, port: &u16) -> Result<Server, ServerError> {
+        let listener = TcpListener::bind((*ip, *port)).await.map_err(ServerError::Bind)?;
+        Ok(Server { listener })
+    }
+
+    pub async fn start(self, route_map: RouteMap) -> Result<(), ServerError> {
+        let route_map = Arc::new(route_map);
+        loop {
+            let (stream, _) = self.listener.accept().await.map_err(ServerError::Accept)?;
+            let io = TokioIo::new(stream);
+            let route_map = route_map.clone();
+            tokio::spawn(async move {
+                let service = service_fn(move |req: Request<Incoming>| {
+                    let route_map = route_map.clone();
+                    async move {
+                        let response = handle(route_map, req).await;
+                        Ok::<_, std::convert::Infallible>(response)
+                    }
+                });
+                if let Err(e) = hyper::server::conn::http1::Builder::new()
+                    .serve_connection(io, service)
+                    .with_upgrades()
+                    .await
+                {
+                    eprintln!("Connection error: {:?}", e);
+                }
+            });
+        }
+    }
+}
+
+async fn handle(route_map: Arc<RouteMap>, mut req: Request<Incoming>) -> Response<Full<Bytes>> {
+    let method = req.method().to_string();
+    let path = req.uri().path().to_string();
+    let query: HashMap<String, String> = req
+        .uri()
+        .query()
+        .map(|q| form_urlencoded::parse(q.as_bytes()).into_owned().collect())
+        .unwrap_or_default();
+    let headers: HashMap<String, String> = req
+        .headers()
+        .iter()
+        .map(|(k, v)| (k.as_str().to_ascii_lowercase(), v.to_str().unwrap_or("").to_string()))
+        .collect();
+    let wants_upgrade = headers
+        .get("upgrade")
+        .map(|v| v.eq_ignore_ascii_case("websocket"))
+        .unwrap_or(false);
+    let upgrade = if wants_upgrade { Some(hyper::upgrade::on(&mut req)) } else { None };
+    let body = match req.into_body().collect().await {
+        Ok(collected) => collected.to_bytes().to_vec(),
+        Err(_) => Vec::new(),
+    };
+    let webe_req = WebeRequest { method, path, params: HashMap::new(), query, headers, body, upgrade };
+    let webe_resp = route_map.dispatch(webe_req).await;
+    let mut builder = Response::builder().status(webe_resp.status);
+    for (name, value) in webe_resp.headers {
+        builder = builder.header(name, value);
+    }
+    builder.body(Full::new(Bytes::from(webe_resp.body))).expect("failed to build response")
+}
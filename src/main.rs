@@ -12,8 +12,10 @@ use std::time::{Duration, SystemTime};
 use tokio::sync::Mutex;
 
 use lib_flashcard::http::*;
-use webe_auth::http::{create_account, login, logout, secure, verify_account};
-use webe_web::responders::{file::FileResponder, options::OptionsResponder, spa::SPAResponder};
+use webe_auth::http::{create_account, login, logout, oauth, secure, verify_account};
+use webe_web::responders::{
+    file::FileResponder, options::OptionsResponder, spa::SPAResponder, ws::WebSocketResponder,
+};
 use webe_web::server::{Route, RouteMap, Server};
 
 #[tokio::main]
@@ -56,11 +58,45 @@ async fn main() {
         id_factory: id_factory.clone(),
     }));
 
+    // load OAuth provider config (optional - only enabled if fully configured in .env)
+    print!("Loading OAuth Provider Config......");
+    let oauth_provider = match (
+        env::var("OAUTH_CLIENT_ID"),
+        env::var("OAUTH_CLIENT_SECRET"),
+        env::var("OAUTH_AUTH_URL"),
+        env::var("OAUTH_TOKEN_URL"),
+        env::var("OAUTH_USERINFO_URL"),
+        env::var("OAUTH_SCOPES"),
+    ) {
+        (
+            Ok(client_id),
+            Ok(client_secret),
+            Ok(auth_url),
+            Ok(token_url),
+            Ok(userinfo_url),
+            Ok(scopes),
+        ) => {
+            println!("Done");
+            Some(webe_auth::oauth::OAuthProvider {
+                client_id,
+                client_secret,
+                auth_url,
+                token_url,
+                userinfo_url,
+                scopes,
+            })
+        }
+        _ => {
+            println!("Skipped (not configured)");
+            None
+        }
+    };
+
     // create the Flash database pool
     print!("Building FLASH Database Connection Pool......");
     let db_connect_string =
         env::var("FLASH_DATABASE_URL").expect("Failed to load Flash DB Connect string from .env");
-    let flash_db_manager = webe_auth::db::new_manager(db_connect_string)
+    let flash_db_manager = lib_flashcard::db::new_manager(db_connect_string)
         .expect("Failed to create Flash Database connection pool");
     println!("Done");
 
@@ -68,8 +104,56 @@ async fn main() {
     let flash_manager = Arc::new(Mutex::new(lib_flashcard::FlashManager {
         db_manager: flash_db_manager,
         id_factory: id_factory.clone(),
+        broadcast: Arc::new(std::sync::Mutex::new(std::collections::HashMap::new())),
     }));
 
+    // start the background maintenance task
+    print!("Starting Background Maintenance Task......");
+    let purge_interval_secs: u64 = env::var("PURGE_INTERVAL_SECONDS")
+        .unwrap_or_else(|_| "3600".to_string())
+        .parse()
+        .expect("Failed to parse PURGE_INTERVAL_SECONDS as u64");
+    let purge_auth_manager = auth_manager.clone();
+    let maintenance_task = tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(purge_interval_secs));
+        loop {
+            interval.tick().await;
+            // Only hold the shared auth_manager lock long enough to clone its (cheap,
+            // pool-backed) handles. The actual purge queries are synchronous DB calls,
+            // so they run on a blocking thread and never hold the lock that every
+            // authenticated request also needs to acquire.
+            let (db_manager, email_manager, id_factory) = {
+                let auth_manager = purge_auth_manager.lock().await;
+                (
+                    auth_manager.db_manager.clone(),
+                    auth_manager.email_manager.clone(),
+                    auth_manager.id_factory.clone(),
+                )
+            };
+            let purge_result = tokio::task::spawn_blocking(move || {
+                let auth_manager = webe_auth::WebeAuth {
+                    db_manager,
+                    email_manager,
+                    id_factory,
+                };
+                if let Err(e) = auth_manager.purge_expired_sessions() {
+                    eprintln!("Failed to purge expired sessions: {:?}", e);
+                }
+                if let Err(e) = auth_manager.purge_expired_verifications() {
+                    eprintln!("Failed to purge expired account verifications: {:?}", e);
+                }
+                if let Err(e) = auth_manager.purge_stale_unverified_accounts() {
+                    eprintln!("Failed to purge stale unverified accounts: {:?}", e);
+                }
+            })
+            .await;
+            if let Err(e) = purge_result {
+                eprintln!("Background maintenance task panicked: {:?}", e);
+            }
+        }
+    });
+    println!("Done");
+
     // create the web server
     print!("Setting up Web Server and Routes......");
     let web_bind_ip = env::var("WEB_BIND_IP").expect("Failed to load Web Server Bind IP from .env");
@@ -117,6 +201,30 @@ async fn main() {
     let logout_responder = logout::LogoutResponder::new(auth_manager.clone());
     route_map.add_route(logout_route, logout_responder);
 
+    let login_2fa_route = Route::new("POST", "/account/login/2fa");
+    let login_2fa_responder = login::Login2FAResponder::new(auth_manager.clone());
+    route_map.add_route(login_2fa_route, login_2fa_responder);
+
+    let set_2fa_route = Route::new("POST", "/account/2fa");
+    let set_2fa_responder = secure::SecureResponder::new(
+        auth_manager.clone(),
+        login::Set2FAResponder::new(auth_manager.clone()),
+    );
+    route_map.add_route(set_2fa_route, set_2fa_responder);
+
+    // -- -- oauth (only wired up when a provider is fully configured in .env)
+    if let Some(oauth_provider) = oauth_provider {
+        let oauth_authorize_route = Route::new("GET", "/account/oauth/authorize");
+        let oauth_authorize_responder =
+            oauth::OAuthAuthorizeResponder::new(oauth_provider.clone());
+        route_map.add_route(oauth_authorize_route, oauth_authorize_responder);
+
+        let oauth_callback_route = Route::new("GET", "/account/oauth/callback");
+        let oauth_callback_responder =
+            oauth::OAuthCallbackResponder::new(auth_manager.clone(), oauth_provider);
+        route_map.add_route(oauth_callback_route, oauth_callback_responder);
+    }
+
     // -- flashcard
     // -- -- deck
     let get_decks_route = Route::new("GET", "/decks");
@@ -154,6 +262,20 @@ async fn main() {
     );
     route_map.add_route(delete_deck_route, delete_deck_responder);
 
+    let import_deck_route = Route::new("POST", "/deck/import");
+    let import_deck_responder = secure::SecureResponder::new(
+        auth_manager.clone(),
+        deck::ImportDeckResponder::new(flash_manager.clone()),
+    );
+    route_map.add_route(import_deck_route, import_deck_responder);
+
+    let export_deck_route = Route::new("GET", "/deck/export/<id>");
+    let export_deck_responder = secure::SecureResponder::new(
+        auth_manager.clone(),
+        deck::ExportDeckResponder::new(flash_manager.clone(), "<id>".to_string()),
+    );
+    route_map.add_route(export_deck_route, export_deck_responder);
+
     // -- -- card
 
     let create_card_route = Route::new("POST", "/card/create");
@@ -198,6 +320,21 @@ async fn main() {
     );
     route_map.add_route(get_deck_scores_route, get_deck_scores_responder);
 
+    let get_deck_due_route = Route::new("GET", "/deck/due/<id>");
+    let get_deck_due_responder = secure::SecureResponder::new(
+        auth_manager.clone(),
+        game::DeckDueResponder::new(flash_manager.clone(), "<id>".to_string()),
+    );
+    route_map.add_route(get_deck_due_route, get_deck_due_responder);
+
+    // -- live sync
+    let ws_route = Route::new("GET", "/ws");
+    let ws_responder = secure::SecureResponder::new(
+        auth_manager.clone(),
+        WebSocketResponder::new(flash_manager.clone()),
+    );
+    route_map.add_route(ws_route, ws_responder);
+
     // -- app
     let file_route = Route::new("GET", "/app/<path>");
     let file_responder = FileResponder::new("./app".to_owned(), "<path>".to_owned())
@@ -214,4 +351,7 @@ async fn main() {
     println!("Done");
     println!("___FLASHCARD SERVER IS RUNNING___");
     let _start_result = web_server.start(route_map).await;
+
+    // stop the background maintenance task now that the server has shut down
+    maintenance_task.abort();
 }